@@ -1,13 +1,22 @@
-pub use ark_ec::CurveGroup;
-pub use ark_ff::{Field, PrimeField};
-pub use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+pub use ark_crypto_primitives::sponge::{
+    constraints::CryptographicSpongeVar,
+    poseidon::{constraints::PoseidonSpongeVar, PoseidonConfig, PoseidonSponge},
+    CryptographicSponge,
+};
+pub use ark_ec::{AffineRepr, CurveGroup, Group};
+pub use ark_ff::{BigInteger, Field, PrimeField, ToConstraintField, Zero};
+pub use ark_r1cs_std::{
+    fields::{fp::FpVar, nonnative::NonNativeFieldVar},
+    prelude::*,
+    ToConstraintFieldGadget,
+};
 pub use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
-pub use ark_serialize::CanonicalSerialize;
+pub use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
 pub use ark_std::marker::PhantomData;
 
 pub type ConstraintF<C> = <<C as CurveGroup>::BaseField as Field>::BasePrimeField;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
 pub struct AggKZGInstances<C: CurveGroup> {
     pub random_scalars: Vec<C::BaseField>,
     // pub indices: Vec<usize>,
@@ -16,11 +25,114 @@ pub struct AggKZGInstances<C: CurveGroup> {
 }
 
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
 pub struct AggKZGWitness<C: CurveGroup> {
     pub group_points: Vec<C::Affine>,
 }
 
+impl<C: CurveGroup> AggKZGInstances<C> {
+    /// Curve-dependent params (the concrete `C`) are supplied at the call site via
+    /// turbofish, so the byte stream itself carries no curve identifier.
+    pub fn write(&self, writer: impl ark_serialize::Write) -> Result<(), SerializationError> {
+        self.serialize_compressed(writer)
+    }
+
+    pub fn read(reader: impl ark_serialize::Read) -> Result<Self, SerializationError> {
+        Self::deserialize_compressed(reader)
+    }
+}
+
+impl<C: CurveGroup> AggKZGWitness<C> {
+    pub fn write(&self, writer: impl ark_serialize::Write) -> Result<(), SerializationError> {
+        self.serialize_compressed(writer)
+    }
+
+    pub fn read(reader: impl ark_serialize::Read) -> Result<Self, SerializationError> {
+        Self::deserialize_compressed(reader)
+    }
+}
+
+impl<C: CurveGroup> serde::Serialize for AggKZGInstances<C> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut bytes = Vec::new();
+        self.serialize_compressed(&mut bytes)
+            .map_err(serde::ser::Error::custom)?;
+        serde_bytes::Bytes::new(&bytes).serialize(serializer)
+    }
+}
+
+impl<'de, C: CurveGroup> serde::Deserialize<'de> for AggKZGInstances<C> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = serde_bytes::ByteBuf::deserialize(deserializer)?;
+        Self::deserialize_compressed(bytes.as_slice()).map_err(serde::de::Error::custom)
+    }
+}
+
+impl<C: CurveGroup> serde::Serialize for AggKZGWitness<C> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut bytes = Vec::new();
+        self.serialize_compressed(&mut bytes)
+            .map_err(serde::ser::Error::custom)?;
+        serde_bytes::Bytes::new(&bytes).serialize(serializer)
+    }
+}
+
+impl<'de, C: CurveGroup> serde::Deserialize<'de> for AggKZGWitness<C> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = serde_bytes::ByteBuf::deserialize(deserializer)?;
+        Self::deserialize_compressed(bytes.as_slice()).map_err(serde::de::Error::custom)
+    }
+}
+
+impl<C: CurveGroup> AggKZGInstances<C>
+where
+    C::BaseField: PrimeField,
+{
+    /// Poseidon hash binding `group_points` to a single field element, used for the
+    /// `commitments` field; computed identically in-circuit by
+    /// [`AggKZGCircuit::generate_constraints`] so the two can be checked against each other.
+    /// Returns `None` if a point's `ToConstraintField` representation is unavailable,
+    /// mirroring the `?`-propagation the gadget performs for the same failure.
+    pub fn commit_group_points(
+        group_points: &[C::Affine],
+        poseidon_config: &PoseidonConfig<C::BaseField>,
+    ) -> Option<C::BaseField> {
+        let mut sponge = PoseidonSponge::<C::BaseField>::new(poseidon_config);
+        for point in group_points {
+            sponge.absorb(&point.to_field_elements()?);
+        }
+        Some(sponge.squeeze_field_elements::<C::BaseField>(1)[0])
+    }
+
+    /// Native reference implementation of the relation enforced by
+    /// [`AggKZGCircuit::generate_constraints`], so tests can assert that a witness
+    /// accepted here also satisfies the R1CS, and vice versa. Checks both `y` and
+    /// `commitments` the same way the gadget does.
+    pub fn verify(
+        &self,
+        witness: &AggKZGWitness<C>,
+        poseidon_config: &PoseidonConfig<C::BaseField>,
+    ) -> bool {
+        if self.random_scalars.len() != witness.group_points.len() {
+            return false;
+        }
+        let acc: C = self
+            .random_scalars
+            .iter()
+            .zip(witness.group_points.iter())
+            .fold(C::zero(), |acc, (scalar, point)| {
+                acc + point.mul_bigint(scalar.into_bigint())
+            });
+        if acc.into_affine() != self.y {
+            return false;
+        }
+        match Self::commit_group_points(&witness.group_points, poseidon_config) {
+            Some(commitments) => commitments == self.commitments,
+            None => false,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct AggKZGCircuit<C: CurveGroup, GG: CurveVar<C, ConstraintF<C>>>
 where
@@ -28,6 +140,7 @@ where
 {
     pub instance: AggKZGInstances<C>,
     pub witness: AggKZGWitness<C>,
+    pub poseidon_config: PoseidonConfig<C::BaseField>,
     pub _curve: PhantomData<GG>,
 }
 
@@ -58,9 +171,794 @@ where
             Ok(self.witness.group_points)
         })?;
 
-        // constraints
-        
+        if random_scalars_var.len() != group_points_var.len() {
+            return Err(SynthesisError::AssignmentMissing);
+        }
+
+        // constraints: y = sum_i scalar_i * group_points_i
+        let mut acc = GG::zero();
+        for (scalar_var, point_var) in random_scalars_var.iter().zip(group_points_var.iter()) {
+            let bits = scalar_var.to_bits_le()?;
+            // `scalar_mul_le`'s complete addition formulas handle the identity point
+            // without an extra conditional select; covered by
+            // `agg_kzg_circuit_tolerates_identity_group_point` below.
+            let term = point_var.scalar_mul_le(bits.iter())?;
+            acc += term;
+        }
+        acc.enforce_equal(&y_var)?;
+
+        // commitments = Poseidon(group_points), checked the same way
+        // `AggKZGInstances::commit_group_points` computes it natively
+        let mut commitment_sponge =
+            PoseidonSpongeVar::<C::BaseField>::new(cs.clone(), &self.poseidon_config);
+        for point_var in &group_points_var {
+            commitment_sponge.absorb(&point_var.to_constraint_field()?)?;
+        }
+        let commitments_computed_var = commitment_sponge.squeeze_field_elements(1)?.remove(0);
+        commitments_computed_var.enforce_equal(&commitments_var)?;
 
         Ok(())
     }
 }
+
+/// Fiat-Shamir variant of [`AggKZGInstances`]: the batching coefficients are not
+/// supplied by the prover but derived in-circuit from a single public challenge.
+/// The challenge is derived only from the public `commitments` field, which is
+/// itself bound in-circuit to the witnessed `group_points`; the prover therefore
+/// cannot choose `group_points` after learning what challenge they would produce.
+#[derive(Clone, Debug)]
+pub struct FiatShamirAggKZGInstances<C: CurveGroup> {
+    pub challenge: C::BaseField,
+    pub y: C::Affine,
+    pub commitments: C::BaseField,
+}
+
+#[derive(Clone)]
+pub struct FiatShamirAggKZGCircuit<C: CurveGroup, GG: CurveVar<C, ConstraintF<C>>>
+where
+    <C as CurveGroup>::BaseField: PrimeField,
+{
+    pub instance: FiatShamirAggKZGInstances<C>,
+    pub witness: AggKZGWitness<C>,
+    pub poseidon_config: PoseidonConfig<ConstraintF<C>>,
+    pub _curve: PhantomData<GG>,
+}
+
+impl<C, GG> ConstraintSynthesizer<C::BaseField> for FiatShamirAggKZGCircuit<C, GG>
+where
+    C: CurveGroup,
+    GG: CurveVar<C, C::BaseField>,
+    <C as CurveGroup>::BaseField: PrimeField,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    fn generate_constraints(
+        self,
+        cs: ConstraintSystemRef<C::BaseField>,
+    ) -> ark_relations::r1cs::Result<()> {
+        // instances
+        let challenge_var = FpVar::<C::BaseField>::new_input(cs.clone(), || {
+            Ok(self.instance.challenge)
+        })?;
+        let y_var = GG::new_input(cs.clone(), || {
+            Ok(self.instance.y)
+        })?;
+        let commitments_var = FpVar::<C::BaseField>::new_input(cs.clone(), || {
+            Ok(self.instance.commitments)
+        })?;
+
+        // witness
+        let group_points_var = Vec::<GG>::new_witness(cs.clone(), || {
+            Ok(self.witness.group_points)
+        })?;
+
+        // bind the public `commitments` field to the witnessed group points, so the
+        // prover cannot change `group_points` without also changing `commitments`
+        let mut commitment_sponge =
+            PoseidonSpongeVar::<C::BaseField>::new(cs.clone(), &self.poseidon_config);
+        for point_var in &group_points_var {
+            commitment_sponge.absorb(&point_var.to_constraint_field()?)?;
+        }
+        let commitments_computed_var = commitment_sponge.squeeze_field_elements(1)?.remove(0);
+        commitments_computed_var.enforce_equal(&commitments_var)?;
+
+        // derive gamma purely from the (now-bound) public `commitments`, never from
+        // the witness directly, so a prover cannot pick `group_points` after seeing
+        // what gamma a given choice would produce
+        let mut gamma_sponge =
+            PoseidonSpongeVar::<C::BaseField>::new(cs.clone(), &self.poseidon_config);
+        gamma_sponge.absorb(&commitments_var)?;
+        let gamma_var = gamma_sponge.squeeze_field_elements(1)?.remove(0);
+        gamma_var.enforce_equal(&challenge_var)?;
+
+        // expand the batching coefficients as powers of gamma: [1, gamma, gamma^2, ...]
+        let mut acc = GG::zero();
+        let mut power = FpVar::<C::BaseField>::one();
+        for point_var in &group_points_var {
+            let bits = power.to_bits_le()?;
+            let term = point_var.scalar_mul_le(bits.iter())?;
+            acc += term;
+            power *= &gamma_var;
+        }
+        acc.enforce_equal(&y_var)?;
+
+        Ok(())
+    }
+}
+
+/// Nonnative-field variant of [`AggKZGInstances`]: the batching coefficients live in
+/// `C::ScalarField`, which is emulated inside the `C::BaseField` constraint system
+/// via [`NonNativeFieldVar`] rather than being (mis)represented as `FpVar<C::BaseField>`.
+#[derive(Clone, Debug)]
+pub struct NonNativeAggKZGInstances<C: CurveGroup> {
+    pub random_scalars: Vec<C::ScalarField>,
+    pub y: C::Affine,
+    pub commitments: C::BaseField,
+}
+
+#[derive(Clone)]
+pub struct NonNativeAggKZGCircuit<C: CurveGroup, GG: CurveVar<C, ConstraintF<C>>>
+where
+    <C as CurveGroup>::BaseField: PrimeField,
+{
+    pub instance: NonNativeAggKZGInstances<C>,
+    pub witness: AggKZGWitness<C>,
+    pub poseidon_config: PoseidonConfig<C::BaseField>,
+    pub _curve: PhantomData<GG>,
+}
+
+impl<C, GG> ConstraintSynthesizer<C::BaseField> for NonNativeAggKZGCircuit<C, GG>
+where
+    C: CurveGroup,
+    GG: CurveVar<C, C::BaseField>,
+    <C as CurveGroup>::BaseField: PrimeField,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    fn generate_constraints(
+        self,
+        cs: ConstraintSystemRef<C::BaseField>,
+    ) -> ark_relations::r1cs::Result<()> {
+        // instances
+        let random_scalars_var =
+            Vec::<NonNativeFieldVar<C::ScalarField, C::BaseField>>::new_input(cs.clone(), || {
+                Ok(self.instance.random_scalars)
+            })?;
+        let y_var = GG::new_input(cs.clone(), || {
+            Ok(self.instance.y)
+        })?;
+        let commitments_var = FpVar::<C::BaseField>::new_input(cs.clone(), || {
+            Ok(self.instance.commitments)
+        })?;
+
+        // witness
+        let group_points_var = Vec::<GG>::new_witness(cs.clone(), || {
+            Ok(self.witness.group_points)
+        })?;
+
+        if random_scalars_var.len() != group_points_var.len() {
+            return Err(SynthesisError::AssignmentMissing);
+        }
+
+        // constraints: y = sum_i scalar_i * group_points_i, scalar_i in C::ScalarField
+        let mut acc = GG::zero();
+        for (scalar_var, point_var) in random_scalars_var.iter().zip(group_points_var.iter()) {
+            let bits = scalar_var.to_bits_le()?;
+            let term = point_var.scalar_mul_le(bits.iter())?;
+            acc += term;
+        }
+        acc.enforce_equal(&y_var)?;
+
+        // commitments = Poseidon(group_points), checked the same way
+        // `AggKZGInstances::commit_group_points` computes it natively
+        let mut commitment_sponge =
+            PoseidonSpongeVar::<C::BaseField>::new(cs.clone(), &self.poseidon_config);
+        for point_var in &group_points_var {
+            commitment_sponge.absorb(&point_var.to_constraint_field()?)?;
+        }
+        let commitments_computed_var = commitment_sponge.squeeze_field_elements(1)?.remove(0);
+        commitments_computed_var.enforce_equal(&commitments_var)?;
+
+        Ok(())
+    }
+}
+
+/// A single CycleFold instance: `result = scalar * point`, expressed as a tiny
+/// R1CS circuit over whichever curve `point`/`result` live on (named `C2` here
+/// since it is generic; [`CycleFoldAccumulator::verify_and_fold`] instantiates
+/// it with the *primary* curve `C`, since that is the curve the delegated
+/// scalar multiplication actually happens on). The scalar is witnessed directly
+/// as bits rather than as a field element, so it may come from any field.
+#[derive(Clone)]
+pub struct CycleFoldCircuit<C2: CurveGroup, GG2: CurveVar<C2, ConstraintF<C2>>>
+where
+    C2::BaseField: PrimeField,
+{
+    pub point: C2::Affine,
+    pub scalar_bits: Vec<bool>,
+    pub result: C2::Affine,
+    pub _curve: PhantomData<GG2>,
+}
+
+impl<C2, GG2> ConstraintSynthesizer<C2::BaseField> for CycleFoldCircuit<C2, GG2>
+where
+    C2: CurveGroup,
+    GG2: CurveVar<C2, C2::BaseField>,
+    C2::BaseField: PrimeField,
+    for<'a> &'a GG2: GroupOpsBounds<'a, C2, GG2>,
+{
+    fn generate_constraints(
+        self,
+        cs: ConstraintSystemRef<C2::BaseField>,
+    ) -> ark_relations::r1cs::Result<()> {
+        let point_var = GG2::new_witness(cs.clone(), || Ok(self.point))?;
+        let bits = Vec::<Boolean<C2::BaseField>>::new_witness(cs.clone(), || Ok(self.scalar_bits))?;
+        let result_var = GG2::new_input(cs.clone(), || Ok(self.result))?;
+
+        let computed = point_var.scalar_mul_le(bits.iter())?;
+        computed.enforce_equal(&result_var)?;
+
+        Ok(())
+    }
+}
+
+/// Running CycleFold accumulator: a single folded commitment on the companion
+/// curve `C2` that stands in for the MSM of every `CycleFoldCircuit` instance
+/// folded into it so far.
+#[derive(Clone, Debug)]
+pub struct CycleFoldAccumulator<C2: CurveGroup> {
+    pub commitment: C2::Affine,
+}
+
+impl<C2: CurveGroup> CycleFoldAccumulator<C2>
+where
+    C2::BaseField: PrimeField,
+{
+    pub fn zero() -> Self {
+        Self {
+            commitment: C2::Affine::zero(),
+        }
+    }
+
+    /// Fold a new `scalar * point` instance into the running accumulator with a
+    /// (Fiat-Shamir derived) random challenge `r`, Nova-style: `acc' = acc + r * incoming`.
+    pub fn fold(&self, incoming: &C2::Affine, r: C2::ScalarField) -> Self {
+        let folded = self.commitment + incoming.mul_bigint(r.into_bigint());
+        Self {
+            commitment: folded.into_affine(),
+        }
+    }
+
+    /// Check a [`CycleFoldCircuit`] instance natively (`result == scalar * point`,
+    /// the same relation its `generate_constraints` enforces) before folding it in;
+    /// returns `None` if the instance's claimed `result` is wrong. Unlike
+    /// [`Self::verify_and_fold`], this only re-checks the scalar-multiplication
+    /// relation with plain curve arithmetic — it does not build or run the R1CS.
+    pub fn fold_checked<GG2>(
+        &self,
+        instance: &CycleFoldCircuit<C2, GG2>,
+        r: C2::ScalarField,
+    ) -> Option<Self>
+    where
+        GG2: CurveVar<C2, ConstraintF<C2>>,
+    {
+        let scalar_repr = <C2::ScalarField as PrimeField>::BigInt::from_bits_le(&instance.scalar_bits);
+        let scalar = C2::ScalarField::from_bigint(scalar_repr)?;
+        let expected = instance.point.mul_bigint(scalar.into_bigint()).into_affine();
+        if expected != instance.result {
+            return None;
+        }
+        Some(self.fold(&instance.result, r))
+    }
+
+    /// Prove `result = scalar * point` by actually building a [`CycleFoldCircuit`]
+    /// instance over the *primary* curve `C` and checking `cs.is_satisfied()` on a
+    /// fresh constraint system, then fold `result` into this accumulator as a
+    /// Pedersen-style commitment on the companion curve `C2`. This is what makes
+    /// `CycleFoldCircuit::generate_constraints` an actual R1CS check rather than
+    /// an unused type, and is the building block [`CycleFoldAggKZGInstances::verify`]
+    /// uses to tie the accumulator to every `(scalar_i, group_points_i)` term.
+    /// Requires the curve-cycle relationship `C2::ScalarField == C::BaseField`
+    /// (e.g. `C2` the BLS12-381 scalar-field curve relative to `C` a curve
+    /// embedded over BLS12-381's scalar field, such as JubJub), so a primary-curve
+    /// point's coordinates are native `C2` scalars.
+    pub fn verify_and_fold<C, GG>(
+        &self,
+        point: C::Affine,
+        scalar: C::BaseField,
+        result: C::Affine,
+        r: C2::ScalarField,
+    ) -> Option<Self>
+    where
+        C: CurveGroup<BaseField = C2::ScalarField>,
+        GG: CurveVar<C, C::BaseField>,
+        for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+    {
+        let instance = CycleFoldCircuit::<C, GG> {
+            point,
+            scalar_bits: scalar.into_bigint().to_bits_le(),
+            result,
+            _curve: PhantomData,
+        };
+        let cs = ark_relations::r1cs::ConstraintSystem::<C::BaseField>::new_ref();
+        instance.generate_constraints(cs.clone()).ok()?;
+        if !cs.is_satisfied().ok()? {
+            return None;
+        }
+
+        let (x, y) = result
+            .xy()
+            .unwrap_or((C::BaseField::zero(), C::BaseField::zero()));
+        let basis = C2::generator();
+        let commitment = basis.mul_bigint(x.into_bigint()) + (basis + basis).mul_bigint(y.into_bigint());
+        Some(self.fold(&commitment.into_affine(), r))
+    }
+}
+
+/// CycleFold variant of [`AggKZGInstances`]/[`AggKZGWitness`]. The batching
+/// coefficients here are typed as `C::BaseField`, the same cheap native case
+/// [`AggKZGCircuit`] already handles, so `generate_constraints` recomputes
+/// `y = Σ scalar_i · group_points_i` directly — [`NonNativeAggKZGCircuit`] is
+/// the case CycleFold is meant to help with. What this variant adds is a
+/// `folded_commitment`: each term is *additionally* proved by its own
+/// [`CycleFoldCircuit`] over `C`, and its result is folded into a
+/// [`CycleFoldAccumulator`] on a companion curve `C2` forming a cycle with `C`
+/// (`C2::ScalarField == C::BaseField`) via [`CycleFoldAccumulator::verify_and_fold`], which actually builds and
+/// checks that small circuit's R1CS rather than trusting an opaque witness.
+/// [`CycleFoldAggKZGInstances::verify`] re-derives that accumulator from the
+/// same `(random_scalars, group_points)` the main circuit witnesses, so a
+/// prover cannot supply a `folded_commitment` unrelated to the actual terms.
+/// `generate_constraints` itself only proves the cheap half (`y`); tying
+/// `folded_commitment` to the terms is the native verifier's job, since the
+/// accumulator's coordinates live in `C2::BaseField`, a field foreign to this
+/// circuit — recursively composing that check into the same R1CS is out of
+/// scope for this toy, non-recursive construction.
+#[derive(Clone, Debug)]
+pub struct CycleFoldAggKZGInstances<C: CurveGroup, C2: CurveGroup> {
+    pub y: C::Affine,
+    pub folded_commitment: C2::BaseField,
+}
+
+#[derive(Clone, Debug)]
+pub struct CycleFoldAggKZGWitness<C: CurveGroup, C2: CurveGroup> {
+    pub random_scalars: Vec<C::BaseField>,
+    pub group_points: Vec<C::Affine>,
+    pub accumulator: CycleFoldAccumulator<C2>,
+}
+
+impl<C, C2> CycleFoldAggKZGInstances<C, C2>
+where
+    C: CurveGroup,
+    C::BaseField: PrimeField,
+    C2: CurveGroup<ScalarField = C::BaseField>,
+    C2::BaseField: PrimeField,
+{
+    /// Native reference check for [`CycleFoldAggKZGCircuit`]: recomputes `y` the
+    /// same way `generate_constraints` does, then re-derives `folded_commitment`
+    /// from scratch by re-proving and re-folding each term via
+    /// [`CycleFoldAccumulator::verify_and_fold`] — rather than trusting the
+    /// witness's `accumulator` or the instance's `folded_commitment` directly. `challenges`
+    /// supplies the per-term folding randomness `r_i` (e.g. Fiat-Shamir derived
+    /// by the caller), one per term.
+    pub fn verify<GG>(
+        &self,
+        witness: &CycleFoldAggKZGWitness<C, C2>,
+        challenges: &[C2::ScalarField],
+        poseidon_config: &PoseidonConfig<C2::BaseField>,
+    ) -> bool
+    where
+        GG: CurveVar<C, C::BaseField>,
+        for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+    {
+        if witness.random_scalars.len() != witness.group_points.len()
+            || witness.random_scalars.len() != challenges.len()
+        {
+            return false;
+        }
+
+        let acc: C = witness
+            .random_scalars
+            .iter()
+            .zip(witness.group_points.iter())
+            .fold(C::zero(), |acc, (scalar, point)| {
+                acc + point.mul_bigint(scalar.into_bigint())
+            });
+        if acc.into_affine() != self.y {
+            return false;
+        }
+
+        let mut accumulator = CycleFoldAccumulator::<C2>::zero();
+        for ((scalar, point), r) in witness
+            .random_scalars
+            .iter()
+            .zip(witness.group_points.iter())
+            .zip(challenges.iter())
+        {
+            let result = point.mul_bigint(scalar.into_bigint()).into_affine();
+            match accumulator.verify_and_fold::<C, GG>(*point, *scalar, result, *r) {
+                Some(next) => accumulator = next,
+                None => return false,
+            }
+        }
+        if accumulator.commitment != witness.accumulator.commitment {
+            return false;
+        }
+
+        let (acc_x, acc_y) = accumulator
+            .commitment
+            .xy()
+            .unwrap_or((C2::BaseField::zero(), C2::BaseField::zero()));
+        let mut sponge = PoseidonSponge::<C2::BaseField>::new(poseidon_config);
+        sponge.absorb(&acc_x);
+        sponge.absorb(&acc_y);
+        let digest: C2::BaseField = sponge.squeeze_field_elements(1)[0];
+        digest == self.folded_commitment
+    }
+}
+
+#[derive(Clone)]
+pub struct CycleFoldAggKZGCircuit<C: CurveGroup, GG: CurveVar<C, ConstraintF<C>>, C2: CurveGroup>
+where
+    <C as CurveGroup>::BaseField: PrimeField,
+    C2::BaseField: PrimeField,
+{
+    pub instance: CycleFoldAggKZGInstances<C, C2>,
+    pub witness: CycleFoldAggKZGWitness<C, C2>,
+    pub _curve: PhantomData<GG>,
+}
+
+impl<C, GG, C2> ConstraintSynthesizer<C::BaseField> for CycleFoldAggKZGCircuit<C, GG, C2>
+where
+    C: CurveGroup,
+    GG: CurveVar<C, C::BaseField>,
+    C::BaseField: PrimeField,
+    C2: CurveGroup<ScalarField = C::BaseField>,
+    C2::BaseField: PrimeField,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    fn generate_constraints(
+        self,
+        cs: ConstraintSystemRef<C::BaseField>,
+    ) -> ark_relations::r1cs::Result<()> {
+        // instances
+        let y_var = GG::new_input(cs.clone(), || Ok(self.instance.y))?;
+
+        // witness: the same terms `CycleFoldAggKZGInstances::verify` re-derives
+        // the companion-curve accumulator from
+        let random_scalars_var = Vec::<FpVar<C::BaseField>>::new_witness(cs.clone(), || {
+            Ok(self.witness.random_scalars)
+        })?;
+        let group_points_var = Vec::<GG>::new_witness(cs.clone(), || {
+            Ok(self.witness.group_points)
+        })?;
+
+        if random_scalars_var.len() != group_points_var.len() {
+            return Err(SynthesisError::AssignmentMissing);
+        }
+
+        // y = sum_i scalar_i * group_points_i; the same terms are independently
+        // proved and folded on the companion curve `C2` by
+        // `CycleFoldAccumulator::verify_and_fold` (see `CycleFoldAggKZGInstances::verify`)
+        let mut acc = GG::zero();
+        for (scalar_var, point_var) in random_scalars_var.iter().zip(group_points_var.iter()) {
+            let bits = scalar_var.to_bits_le()?;
+            let term = point_var.scalar_mul_le(bits.iter())?;
+            acc += term;
+        }
+        acc.enforce_equal(&y_var)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ed_on_bls12_381::{constraints::EdwardsVar, EdwardsProjective as JubJub, Fq, Fr};
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::{test_rng, UniformRand};
+
+    /// A fixed (non-cryptographic) Poseidon config shared by the tests in this
+    /// module, so native and in-circuit hashing agree on the same parameters.
+    fn test_poseidon_config<F: PrimeField>() -> PoseidonConfig<F> {
+        let rate = 2;
+        let capacity = 1;
+        let full_rounds = 8;
+        let partial_rounds = 31;
+        let alpha = 5;
+        let mds = vec![vec![F::one(); rate + capacity]; rate + capacity];
+        let ark = vec![vec![F::one(); rate + capacity]; full_rounds + partial_rounds];
+        PoseidonConfig::new(full_rounds, partial_rounds, alpha, mds, ark, rate, capacity)
+    }
+
+    #[test]
+    fn agg_kzg_native_verify_matches_circuit() {
+        let poseidon_config = test_poseidon_config::<Fq>();
+        let mut rng = test_rng();
+        let n = 4;
+        let group_points: Vec<_> = (0..n)
+            .map(|_| JubJub::rand(&mut rng).into_affine())
+            .collect();
+        let random_scalars: Vec<Fq> = (0..n).map(|_| Fq::rand(&mut rng)).collect();
+
+        let y: JubJub = random_scalars
+            .iter()
+            .zip(group_points.iter())
+            .fold(JubJub::zero(), |acc, (s, p)| {
+                acc + p.mul_bigint(s.into_bigint())
+            });
+        let commitments = AggKZGInstances::<JubJub>::commit_group_points(
+            &group_points,
+            &poseidon_config,
+        )
+        .unwrap();
+
+        let instance = AggKZGInstances::<JubJub> {
+            random_scalars,
+            y: y.into_affine(),
+            commitments,
+        };
+        let witness = AggKZGWitness::<JubJub> { group_points };
+
+        assert!(instance.verify(&witness, &poseidon_config));
+
+        let circuit = AggKZGCircuit::<JubJub, EdwardsVar> {
+            instance,
+            witness,
+            poseidon_config,
+            _curve: PhantomData,
+        };
+
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn agg_kzg_instances_and_witness_serde_round_trip() {
+        let mut rng = test_rng();
+        let n = 4;
+        let group_points: Vec<_> = (0..n)
+            .map(|_| JubJub::rand(&mut rng).into_affine())
+            .collect();
+        let random_scalars: Vec<Fq> = (0..n).map(|_| Fq::rand(&mut rng)).collect();
+        let y: JubJub = random_scalars
+            .iter()
+            .zip(group_points.iter())
+            .fold(JubJub::zero(), |acc, (s, p)| {
+                acc + p.mul_bigint(s.into_bigint())
+            });
+
+        let instance = AggKZGInstances::<JubJub> {
+            random_scalars,
+            y: y.into_affine(),
+            commitments: Fq::rand(&mut rng),
+        };
+        let witness = AggKZGWitness::<JubJub> { group_points };
+
+        let mut instance_bytes = Vec::new();
+        instance.write(&mut instance_bytes).unwrap();
+        let instance_roundtrip = AggKZGInstances::<JubJub>::read(&instance_bytes[..]).unwrap();
+        assert_eq!(instance.random_scalars, instance_roundtrip.random_scalars);
+        assert_eq!(instance.y, instance_roundtrip.y);
+        assert_eq!(instance.commitments, instance_roundtrip.commitments);
+
+        let mut witness_bytes = Vec::new();
+        witness.write(&mut witness_bytes).unwrap();
+        let witness_roundtrip = AggKZGWitness::<JubJub>::read(&witness_bytes[..]).unwrap();
+        assert_eq!(witness.group_points, witness_roundtrip.group_points);
+
+        let instance_json = serde_json::to_vec(&instance).unwrap();
+        let instance_from_json: AggKZGInstances<JubJub> =
+            serde_json::from_slice(&instance_json).unwrap();
+        assert_eq!(instance.y, instance_from_json.y);
+    }
+
+    #[test]
+    fn agg_kzg_circuit_tolerates_identity_group_point() {
+        let poseidon_config = test_poseidon_config::<Fq>();
+        let mut rng = test_rng();
+
+        // a *nonzero* scalar against the identity point (the case that can break
+        // incomplete/short-Weierstrass addition formulas, unlike 0 * anything),
+        // summed with a regular nonzero term so the identity term's contribution
+        // is actually exercised by the running accumulator rather than being a
+        // no-op from the start
+        let other_point = JubJub::rand(&mut rng).into_affine();
+        let other_scalar = Fq::rand(&mut rng);
+        let group_points = vec![JubJub::zero().into_affine(), other_point];
+        let random_scalars = vec![Fq::rand(&mut rng), other_scalar];
+
+        let y = other_point.mul_bigint(other_scalar.into_bigint());
+        let commitments =
+            AggKZGInstances::<JubJub>::commit_group_points(&group_points, &poseidon_config)
+                .unwrap();
+
+        let instance = AggKZGInstances::<JubJub> {
+            random_scalars,
+            y: y.into_affine(),
+            commitments,
+        };
+        let witness = AggKZGWitness::<JubJub> { group_points };
+
+        let circuit = AggKZGCircuit::<JubJub, EdwardsVar> {
+            instance,
+            witness,
+            poseidon_config,
+            _curve: PhantomData,
+        };
+
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn fiat_shamir_agg_kzg_circuit_accepts_honest_prover() {
+        let poseidon_config = test_poseidon_config::<Fq>();
+        let mut rng = test_rng();
+        let n = 4;
+        let group_points: Vec<_> = (0..n)
+            .map(|_| JubJub::rand(&mut rng).into_affine())
+            .collect();
+
+        let commitments =
+            AggKZGInstances::<JubJub>::commit_group_points(&group_points, &poseidon_config)
+                .unwrap();
+        let mut native_sponge = PoseidonSponge::<Fq>::new(&poseidon_config);
+        native_sponge.absorb(&commitments);
+        let gamma: Fq = native_sponge.squeeze_field_elements(1)[0];
+
+        let y: JubJub = group_points.iter().enumerate().fold(
+            JubJub::zero(),
+            |acc, (i, p)| acc + p.mul_bigint(gamma.pow([i as u64]).into_bigint()),
+        );
+
+        let instance = FiatShamirAggKZGInstances::<JubJub> {
+            challenge: gamma,
+            y: y.into_affine(),
+            commitments,
+        };
+        let witness = AggKZGWitness::<JubJub> { group_points };
+
+        let circuit = FiatShamirAggKZGCircuit::<JubJub, EdwardsVar> {
+            instance,
+            witness,
+            poseidon_config,
+            _curve: PhantomData,
+        };
+
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn nonnative_agg_kzg_circuit_accepts_honest_prover() {
+        // JubJub's scalar field (Fr) and base field (Fq) are distinct, so this
+        // exercises the nonnative bit decomposition `NonNativeAggKZGCircuit` is
+        // meant for, unlike the other aggregation-circuit tests here, which type
+        // scalars as `Fq`.
+        let poseidon_config = test_poseidon_config::<Fq>();
+        let mut rng = test_rng();
+        let n = 4;
+        let group_points: Vec<_> = (0..n)
+            .map(|_| JubJub::rand(&mut rng).into_affine())
+            .collect();
+        let random_scalars: Vec<Fr> = (0..n).map(|_| Fr::rand(&mut rng)).collect();
+
+        let y: JubJub = random_scalars
+            .iter()
+            .zip(group_points.iter())
+            .fold(JubJub::zero(), |acc, (s, p)| {
+                acc + p.mul_bigint(s.into_bigint())
+            });
+        let commitments =
+            AggKZGInstances::<JubJub>::commit_group_points(&group_points, &poseidon_config)
+                .unwrap();
+
+        let instance = NonNativeAggKZGInstances::<JubJub> {
+            random_scalars,
+            y: y.into_affine(),
+            commitments,
+        };
+        let witness = AggKZGWitness::<JubJub> { group_points };
+
+        let circuit = NonNativeAggKZGCircuit::<JubJub, EdwardsVar> {
+            instance,
+            witness,
+            poseidon_config,
+            _curve: PhantomData,
+        };
+
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn cyclefold_circuit_checks_scalar_multiplication() {
+        let mut rng = test_rng();
+        let point = JubJub::rand(&mut rng).into_affine();
+        let scalar = Fr::rand(&mut rng);
+        let result = point.mul_bigint(scalar.into_bigint()).into_affine();
+
+        let instance = CycleFoldCircuit::<JubJub, EdwardsVar> {
+            point,
+            scalar_bits: scalar.into_bigint().to_bits_le(),
+            result,
+            _curve: PhantomData,
+        };
+
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        instance.generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn cyclefold_agg_kzg_circuit_ties_accumulator_to_terms() {
+        // a genuine two-curve pair, not `C2 = C`: JubJub's base field is exactly
+        // BLS12-381's scalar field, so a JubJub point's coordinates are native
+        // scalars for BLS12-381's G1 group, satisfying `C2::ScalarField == C::BaseField`
+        use ark_bls12_381::{Fq as Bls12_381Fq, G1Projective as Bls12_381G1};
+
+        let cyclefold_poseidon_config = test_poseidon_config::<Bls12_381Fq>();
+        let mut rng = test_rng();
+        let n = 3;
+        let group_points: Vec<_> = (0..n)
+            .map(|_| JubJub::rand(&mut rng).into_affine())
+            .collect();
+        let random_scalars: Vec<Fq> = (0..n).map(|_| Fq::rand(&mut rng)).collect();
+        let challenges: Vec<_> = (0..n)
+            .map(|_| ark_bls12_381::Fr::rand(&mut rng))
+            .collect();
+
+        let y: JubJub = random_scalars
+            .iter()
+            .zip(group_points.iter())
+            .fold(JubJub::zero(), |acc, (s, p)| {
+                acc + p.mul_bigint(s.into_bigint())
+            });
+
+        // fold each term in via `verify_and_fold`, which actually builds and
+        // checks a `CycleFoldCircuit` R1CS per term rather than trusting the
+        // claimed result natively
+        let mut accumulator = CycleFoldAccumulator::<Bls12_381G1>::zero();
+        for ((scalar, point), r) in random_scalars
+            .iter()
+            .zip(group_points.iter())
+            .zip(challenges.iter())
+        {
+            let result = point.mul_bigint(scalar.into_bigint()).into_affine();
+            accumulator = accumulator
+                .verify_and_fold::<JubJub, EdwardsVar>(*point, *scalar, result, *r)
+                .expect("each term's CycleFoldCircuit must be satisfied");
+        }
+
+        let (acc_x, acc_y) = accumulator.commitment.xy().unwrap();
+        let mut sponge = PoseidonSponge::<Bls12_381Fq>::new(&cyclefold_poseidon_config);
+        sponge.absorb(&acc_x);
+        sponge.absorb(&acc_y);
+        let folded_commitment: Bls12_381Fq = sponge.squeeze_field_elements(1)[0];
+
+        let instance = CycleFoldAggKZGInstances::<JubJub, Bls12_381G1> {
+            y: y.into_affine(),
+            folded_commitment,
+        };
+        let witness = CycleFoldAggKZGWitness::<JubJub, Bls12_381G1> {
+            random_scalars,
+            group_points,
+            accumulator,
+        };
+
+        assert!(instance.verify::<EdwardsVar>(&witness, &challenges, &cyclefold_poseidon_config));
+
+        let circuit = CycleFoldAggKZGCircuit::<JubJub, EdwardsVar, Bls12_381G1> {
+            instance,
+            witness,
+            _curve: PhantomData,
+        };
+
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+    }
+}